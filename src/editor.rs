@@ -1,18 +1,174 @@
 use crate::buffer::{Buffer, BufferType, Mark};
 use crate::display::Display;
-use crate::editor::EditorMode::{Normal, SaveMode};
+use crate::editor::EditorMode::{Normal, SaveMode, SearchMode};
 use crossterm::cursor::{MoveTo, RestorePosition, SavePosition};
 use crossterm::event::Event::Key;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, DisableLineWrap, EnterAlternateScreen, LeaveAlternateScreen};
 use crossterm::{cursor, event, execute, ExecutableCommand};
+use ropey::Rope;
 use std::cmp::PartialEq;
+use std::collections::HashMap;
 use std::fs::OpenOptions;
-use std::io::{Error, Read, Write};
+use std::io::Error;
 use std::time::Duration;
-use log::{error, info};
+use log::{error, info, warn};
 
-const TAB_SIZE: u16 = 4;
+const KEYMAP_CONFIG_PATH: &str = "keybindings.conf";
+const QUIT_TIMES: u8 = 3;
+
+pub type Action = fn(&mut Editor) -> Result<(), Error>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+}
+
+#[derive(Debug)]
+pub struct KeyMap {
+    bindings: HashMap<KeyChord, Action>,
+}
+
+impl KeyMap {
+    pub fn with_defaults() -> Self {
+        let actions = named_actions();
+        let mut bindings = HashMap::new();
+        bindings.insert(KeyChord::new(KeyCode::Char('q'), KeyModifiers::CONTROL), actions["quit"]);
+        bindings.insert(KeyChord::new(KeyCode::Char('x'), KeyModifiers::CONTROL), actions["save"]);
+        bindings.insert(KeyChord::new(KeyCode::Char('z'), KeyModifiers::CONTROL), actions["undo"]);
+        bindings.insert(KeyChord::new(KeyCode::Char('y'), KeyModifiers::CONTROL), actions["redo"]);
+        bindings.insert(KeyChord::new(KeyCode::Char('f'), KeyModifiers::CONTROL), actions["search"]);
+        bindings.insert(KeyChord::new(KeyCode::Right, KeyModifiers::CONTROL), actions["move_next_word"]);
+        bindings.insert(KeyChord::new(KeyCode::Left, KeyModifiers::CONTROL), actions["move_prev_word"]);
+        bindings.insert(KeyChord::new(KeyCode::Char('e'), KeyModifiers::CONTROL), actions["move_next_word_end"]);
+        bindings.insert(
+            KeyChord::new(KeyCode::Right, KeyModifiers::CONTROL | KeyModifiers::SHIFT),
+            actions["move_next_long_word"],
+        );
+        bindings.insert(
+            KeyChord::new(KeyCode::Left, KeyModifiers::CONTROL | KeyModifiers::SHIFT),
+            actions["move_prev_long_word"],
+        );
+        bindings.insert(
+            KeyChord::new(KeyCode::Char('e'), KeyModifiers::CONTROL | KeyModifiers::SHIFT),
+            actions["move_next_long_word_end"],
+        );
+        bindings.insert(KeyChord::new(KeyCode::Right, KeyModifiers::NONE), actions["move_right"]);
+        bindings.insert(KeyChord::new(KeyCode::Left, KeyModifiers::NONE), actions["move_left"]);
+        bindings.insert(KeyChord::new(KeyCode::Up, KeyModifiers::NONE), actions["move_up"]);
+        bindings.insert(KeyChord::new(KeyCode::Down, KeyModifiers::NONE), actions["move_down"]);
+        bindings.insert(KeyChord::new(KeyCode::Backspace, KeyModifiers::NONE), actions["backspace"]);
+        bindings.insert(KeyChord::new(KeyCode::Enter, KeyModifiers::NONE), actions["enter"]);
+        bindings.insert(KeyChord::new(KeyCode::Tab, KeyModifiers::NONE), actions["tab"]);
+        Self { bindings }
+    }
+
+    pub fn load(path: &str) -> Self {
+        let mut keymap = Self::with_defaults();
+        let actions = named_actions();
+        match std::fs::read_to_string(path) {
+            Ok(content) => {
+                for line in content.lines() {
+                    let line = line.trim();
+                    if line.is_empty() || line.starts_with('#') {
+                        continue;
+                    }
+                    match line.split_once('=') {
+                        Some((chord_str, action_name)) => {
+                            let chord = parse_chord(chord_str.trim());
+                            let action = actions.get(action_name.trim());
+                            match (chord, action) {
+                                (Some(chord), Some(action)) => {
+                                    keymap.bindings.insert(chord, *action);
+                                }
+                                _ => warn!("Ignoring invalid keybinding line: {:?}", line),
+                            }
+                        }
+                        None => warn!("Ignoring malformed keybinding line: {:?}", line),
+                    }
+                }
+            }
+            Err(_) => info!("No keybindings config at {:?}, using defaults", path),
+        }
+        keymap
+    }
+
+    pub fn lookup(&self, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        self.bindings.get(&KeyChord::new(code, modifiers)).copied()
+    }
+}
+
+fn parse_chord(s: &str) -> Option<KeyChord> {
+    let mut parts: Vec<&str> = s.split('-').collect();
+    let key_part = parts.pop()?;
+    let mut modifiers = KeyModifiers::NONE;
+    for part in parts {
+        match part.to_ascii_lowercase().as_str() {
+            "ctrl" => modifiers |= KeyModifiers::CONTROL,
+            "shift" => modifiers |= KeyModifiers::SHIFT,
+            "alt" => modifiers |= KeyModifiers::ALT,
+            _ => return None,
+        }
+    }
+    let code = match key_part {
+        "Right" => KeyCode::Right,
+        "Left" => KeyCode::Left,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Backspace" => KeyCode::Backspace,
+        "Enter" => KeyCode::Enter,
+        "Tab" => KeyCode::Tab,
+        s if s.chars().count() == 1 => KeyCode::Char(s.chars().next()?),
+        _ => return None,
+    };
+    Some(KeyChord::new(code, modifiers))
+}
+
+fn action_quit(editor: &mut Editor) -> Result<(), Error> {
+    editor.handle_quit_request()
+}
+
+fn named_actions() -> HashMap<&'static str, Action> {
+    let mut actions: HashMap<&'static str, Action> = HashMap::new();
+    actions.insert("quit", action_quit);
+    actions.insert("save", |editor| {
+        if editor.mode == Normal {
+            editor.handle_save_mode_input()?;
+        }
+        Ok(())
+    });
+    actions.insert("search", |editor| {
+        if editor.mode == Normal {
+            editor.handle_search_mode_input()?;
+        }
+        Ok(())
+    });
+    actions.insert("undo", Editor::handle_undo);
+    actions.insert("redo", Editor::handle_redo);
+    actions.insert("move_right", |editor| editor.handle_cursor_movement(CursorMovement::Right));
+    actions.insert("move_left", |editor| editor.handle_cursor_movement(CursorMovement::Left));
+    actions.insert("move_up", |editor| editor.handle_cursor_movement(CursorMovement::Up));
+    actions.insert("move_down", |editor| editor.handle_cursor_movement(CursorMovement::Down));
+    actions.insert("move_next_word", |editor| editor.handle_cursor_movement(CursorMovement::NextWordStart));
+    actions.insert("move_prev_word", |editor| editor.handle_cursor_movement(CursorMovement::PrevWordStart));
+    actions.insert("move_next_word_end", |editor| editor.handle_cursor_movement(CursorMovement::NextWordEnd));
+    actions.insert("move_next_long_word", |editor| editor.handle_cursor_movement(CursorMovement::NextLongWordStart));
+    actions.insert("move_prev_long_word", |editor| editor.handle_cursor_movement(CursorMovement::PrevLongWordStart));
+    actions.insert("move_next_long_word_end", |editor| {
+        editor.handle_cursor_movement(CursorMovement::NextLongWordEnd)
+    });
+    actions.insert("backspace", Editor::handle_backspace_input);
+    actions.insert("enter", Editor::handle_enter_input);
+    actions.insert("tab", Editor::handle_tab_input);
+    actions
+}
 
 #[derive(Debug)]
 pub struct Editor {
@@ -21,21 +177,66 @@ pub struct Editor {
     pub current_buffer: usize,
     pub previous_buffer: usize,
     pub buffer_list: Vec<Buffer>,
-    pub mode: EditorMode
+    pub mode: EditorMode,
+    pub undo_stack: Vec<UndoEntry>,
+    pub redo_stack: Vec<UndoEntry>,
+    pub saved_undo_depth: usize,
+    pub keymap: KeyMap,
+    pub search_origin: Option<(u16, u16)>,
+    pub search_origin_scroll: u16,
+    pub search_anchor: usize,
+    pub quit_confirmations_remaining: u8,
 }
 
-#[derive(PartialEq)]
+#[derive(Debug, Clone)]
+pub enum EditAction {
+    Insert { at: usize, text: String },
+    Delete { at: usize, text: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub action: EditAction,
+    pub point_before: (u16, u16),
+    pub point_after: (u16, u16),
+}
+
+#[derive(PartialEq, Clone, Copy)]
 pub enum CursorMovement {
     Up,
     Down,
     Left,
     Right,
+    NextWordStart,
+    PrevWordStart,
+    NextWordEnd,
+    NextLongWordStart,
+    PrevLongWordStart,
+    NextLongWordEnd,
+}
+
+#[derive(PartialEq, Eq)]
+enum CharClass {
+    Whitespace,
+    Word,
+    Punctuation,
+}
+
+fn char_class(c: char, long: bool) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Whitespace
+    } else if long || c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub enum EditorMode {
     Normal,
-    SaveMode
+    SaveMode,
+    SearchMode,
 }
 
 impl Editor {
@@ -48,19 +249,26 @@ impl Editor {
             current_buffer: 1,
             buffer_list: vec! [option_buffer, Buffer::default()],
             mode: Normal,
+            undo_stack: vec![],
+            redo_stack: vec![],
+            saved_undo_depth: 0,
+            keymap: KeyMap::load(KEYMAP_CONFIG_PATH),
+            search_origin: None,
+            search_origin_scroll: 0,
+            search_anchor: 0,
+            quit_confirmations_remaining: 0,
         }
     }
 
     pub fn init(&mut self, file_path: Option<String>) ->Result<(), Error> {
         if let Some(file) = file_path.as_ref() {
-            let mut file = OpenOptions::new()
+            let file = OpenOptions::new()
                 .create(true)
                 .read(true)
                 .write(true)
                 .open(file)?;
 
-            let mut content = String::new();
-            file.read_to_string(&mut content)?;
+            let content = Rope::from_reader(&file)?;
 
             if let Some(buffer) = self.buffer_list.get_mut(self.current_buffer) {
                 buffer.content = content;
@@ -75,11 +283,12 @@ impl Editor {
 
     pub fn init_option_buffer() -> Buffer {
         Buffer {
-            content: String::new(),
+            content: Rope::new(),
             point: Mark::new(String::from("Point"), 0),
             mark_list: vec![],
             file_name: None,
             buffer_type: BufferType::OPTION,
+            modified: false,
         }
     }
 
@@ -95,6 +304,30 @@ impl Editor {
         Ok(())
     }
 
+    pub fn handle_quit_request(&mut self) -> Result<(), Error> {
+        let edited_buffer = if self.mode == SaveMode {
+            self.previous_buffer
+        } else {
+            self.current_buffer
+        };
+        if !self.buffer_list[edited_buffer].modified {
+            self.exit = true;
+            return Ok(());
+        }
+
+        if self.quit_confirmations_remaining == 0 {
+            self.quit_confirmations_remaining = QUIT_TIMES;
+        }
+        self.quit_confirmations_remaining -= 1;
+
+        if self.quit_confirmations_remaining == 0 {
+            self.exit = true;
+        } else {
+            self.display.print_quit_warning(self.quit_confirmations_remaining)?;
+        }
+        Ok(())
+    }
+
     pub fn handle_key_events(&mut self) -> Result<(), Error> {
         loop {
             if event::poll(Duration::from_millis(100))? {
@@ -103,24 +336,19 @@ impl Editor {
                         self.handle_resizing(width, height)?;
                     }
                     Key(KeyEvent { code, modifiers, .. }) => {
-                        match code {
-                            KeyCode::Char('q') if modifiers.contains(KeyModifiers::CONTROL) => {
-                                self.exit = true;
-                            },
-                            KeyCode::Char('x') if modifiers.contains(KeyModifiers::CONTROL) && self.mode == Normal => {
-                                self.handle_save_mode_input()?;
+                        if self.mode == SearchMode {
+                            self.quit_confirmations_remaining = 0;
+                            self.handle_search_mode_key(code, modifiers)?;
+                        } else if let Some(action) = self.keymap.lookup(code, modifiers) {
+                            if !std::ptr::fn_addr_eq(action, action_quit as Action) {
+                                self.quit_confirmations_remaining = 0;
                             }
-                            KeyCode::Char(c) if modifiers.is_empty() || modifiers ==KeyModifiers::SHIFT => {
+                            action(self)?;
+                        } else if let KeyCode::Char(c) = code {
+                            if modifiers.is_empty() || modifiers == KeyModifiers::SHIFT {
+                                self.quit_confirmations_remaining = 0;
                                 self.handle_char_input(c)?;
                             }
-                            KeyCode::Right => self.handle_cursor_movement(CursorMovement::Right)?,
-                            KeyCode::Left => self.handle_cursor_movement(CursorMovement::Left)?,
-                            KeyCode::Up => self.handle_cursor_movement(CursorMovement::Up)?,
-                            KeyCode::Down => self.handle_cursor_movement(CursorMovement::Down)?,
-                            KeyCode::Backspace => self.handle_backspace_input()?,
-                            KeyCode::Enter => self.handle_enter_input()?,
-                            KeyCode::Tab => self.handle_tab_input()?,
-                            _ => (),
                         }
                     }
                     _ => (),
@@ -137,7 +365,7 @@ impl Editor {
         self.display.height = height;
         self.display.width = width;
         if let Some((row, col)) = self.buffer_list[self.current_buffer].get_point_line_and_column() {
-            self.display.clear_and_print(self.buffer_list[self.current_buffer].content.clone())?;
+            self.display.clear_and_print(self.buffer_list[self.current_buffer].content.to_string())?;
             execute!(self.display.stdout, MoveTo(col, row))?;
         }
         Ok(())
@@ -158,10 +386,93 @@ impl Editor {
             CursorMovement::Right => {
                 self.handle_cursor_right(col, row)?;
             }
+            CursorMovement::NextWordStart
+            | CursorMovement::PrevWordStart
+            | CursorMovement::NextWordEnd
+            | CursorMovement::NextLongWordStart
+            | CursorMovement::PrevLongWordStart
+            | CursorMovement::NextLongWordEnd => {
+                self.handle_word_motion(movement)?;
+            }
         }
         Ok(())
     }
 
+    fn handle_word_motion(&mut self, movement: CursorMovement) -> Result<(), Error> {
+        let long = matches!(
+            movement,
+            CursorMovement::NextLongWordStart | CursorMovement::PrevLongWordStart | CursorMovement::NextLongWordEnd
+        );
+        let at = self.buffer_list[self.current_buffer].get_point_offset();
+        let target = match movement {
+            CursorMovement::NextWordStart | CursorMovement::NextLongWordStart => self.move_next_word_start(at, long),
+            CursorMovement::PrevWordStart | CursorMovement::PrevLongWordStart => self.move_prev_word_start(at, long),
+            CursorMovement::NextWordEnd | CursorMovement::NextLongWordEnd => self.move_next_word_end(at, long),
+            _ => at,
+        };
+        let (new_row, new_col) = self.buffer_list[self.current_buffer].offset_to_point(target);
+        self.buffer_list[self.current_buffer].move_point_to(new_row, new_col);
+        if new_row < self.display.first_line_visible {
+            self.display.first_line_visible = new_row;
+        } else if new_row - self.display.first_line_visible >= self.display.height {
+            self.display.first_line_visible = new_row - self.display.height + 1;
+        }
+        self.display_current_buffer()?;
+        let render_col = self.buffer_list[self.current_buffer].render_column(new_row, new_col);
+        self.display.stdout.execute(MoveTo(render_col, new_row - self.display.first_line_visible))?;
+        Ok(())
+    }
+
+    fn move_next_word_start(&self, at: usize, long: bool) -> usize {
+        let content = &self.buffer_list[self.current_buffer].content;
+        let len = content.len_chars();
+        if at >= len {
+            return len;
+        }
+        let mut i = at;
+        let start_class = char_class(content.char(i), long);
+        while i < len && char_class(content.char(i), long) == start_class {
+            i += 1;
+        }
+        while i < len && char_class(content.char(i), long) == CharClass::Whitespace {
+            i += 1;
+        }
+        i
+    }
+
+    fn move_prev_word_start(&self, at: usize, long: bool) -> usize {
+        let content = &self.buffer_list[self.current_buffer].content;
+        let mut i = at;
+        while i > 0 && char_class(content.char(i - 1), long) == CharClass::Whitespace {
+            i -= 1;
+        }
+        if i == 0 {
+            return 0;
+        }
+        let class = char_class(content.char(i - 1), long);
+        while i > 0 && char_class(content.char(i - 1), long) == class {
+            i -= 1;
+        }
+        i
+    }
+
+    fn move_next_word_end(&self, at: usize, long: bool) -> usize {
+        let content = &self.buffer_list[self.current_buffer].content;
+        let len = content.len_chars();
+        if len == 0 {
+            return 0;
+        }
+        let mut i = (at + 1).min(len - 1);
+        while i < len - 1 && char_class(content.char(i), long) == CharClass::Whitespace {
+            i += 1;
+        }
+        let class = char_class(content.char(i), long);
+        while i + 1 < len && char_class(content.char(i + 1), long) == class {
+            i += 1;
+        }
+        i
+    }
+
     fn handle_cursor_right(&mut self, col: u16, row: u16) -> Result<(), Error> {
         if let Some((new_row, new_col)) = self.get_cursor_valid_position(
             row + self.display.first_line_visible,
@@ -267,6 +578,7 @@ impl Editor {
                                 None
                             }
                         }
+                        _ => Some((row, *occupied)),
                     }
                 }
             },
@@ -300,26 +612,39 @@ impl Editor {
     }
 
     pub fn handle_char_input(&mut self, c: char) -> Result<(), Error> {
+        let point_before = self.buffer_list[self.current_buffer].get_point_line_and_column().unwrap_or((0, 0));
+        let at = self.buffer_list[self.current_buffer].get_point_offset();
         self.buffer_list[self.current_buffer].write_char(c)?;
-        let (col, row) = cursor::position()?;
+        let (_, row) = cursor::position()?;
         self.display_current_buffer()?;
-        self.buffer_list[self.current_buffer].move_point_to(row + self.display.first_line_visible, col + 1);
-        self.display.stdout.execute(MoveTo(col + 1, row))?;
+        let new_row = row + self.display.first_line_visible;
+        // The logical column counts chars, so it advances by one for the typed
+        // char; the render column accounts for wide (e.g. CJK) or zero-width
+        // (combining) characters.
+        let new_col = point_before.1 + 1;
+        self.buffer_list[self.current_buffer].move_point_to(new_row, new_col);
+        self.push_undo(EditAction::Insert { at, text: c.to_string() }, point_before, (new_row, new_col));
+        let render_col = self.buffer_list[self.current_buffer].render_column(new_row, new_col);
+        self.display.stdout.execute(MoveTo(render_col, row))?;
         Ok(())
     }
 
     pub fn handle_enter_input(&mut self) -> Result<(), Error> {
         if self.mode == Normal {
             let (_, row) = cursor::position()?;
+            let point_before = self.buffer_list[self.current_buffer].get_point_line_and_column().unwrap_or((row + self.display.first_line_visible, 0));
+            let at = self.buffer_list[self.current_buffer].get_point_offset();
             self.buffer_list[self.current_buffer].write_char('\n')?;
             if row + 1 == self.display.height {
                 self.display.first_line_visible = self.display.first_line_visible + 1;
             }
-            self.buffer_list[self.current_buffer].move_point_to(self.display.first_line_visible + row + 1, 0);
+            let new_row = self.display.first_line_visible + row + 1;
+            self.buffer_list[self.current_buffer].move_point_to(new_row, 0);
             self.display_current_buffer()?;
+            self.push_undo(EditAction::Insert { at, text: "\n".to_string() }, point_before, (new_row, 0));
             self.display.stdout.execute(MoveTo(0, row + 1))?;
         } else if self.mode == SaveMode {
-            self.buffer_list[self.previous_buffer].file_name = Some(self.buffer_list[0].content.clone());
+            self.buffer_list[self.previous_buffer].file_name = Some(self.buffer_list[0].content.to_string());
             self.current_buffer = self.previous_buffer;
             self.previous_buffer = 0;
             self.handle_save_file()?;
@@ -330,30 +655,127 @@ impl Editor {
     pub fn handle_backspace_input(&mut self) -> Result<(), Error> {
         let (col, row) = cursor::position()?;
         let first_visible_row = self.display.first_line_visible;
+        let point_before = self.buffer_list[self.current_buffer].get_point_line_and_column().unwrap_or((row + first_visible_row, col));
+        let at = self.buffer_list[self.current_buffer].get_point_offset();
+        if at == 0 {
+            return Ok(());
+        }
         if row > 0 && col == 0 { // remove last character from previous line
+            let removed = self.buffer_list[self.current_buffer].content.char(at - 1);
             let new_row = row - 1;
             let new_col = self.buffer_list[self.current_buffer].get_last_column(new_row);
             self.buffer_list[self.current_buffer].move_point_to(new_row + first_visible_row, new_col);
             self.buffer_list[self.current_buffer].remove_char()?;
             self.display_current_buffer()?;
-            self.display.stdout.execute(MoveTo(new_col - 1, new_row))?;
+            self.push_undo(EditAction::Delete { at: at - 1, text: removed.to_string() }, point_before, (new_row + first_visible_row, new_col));
+            let render_col = self.buffer_list[self.current_buffer].render_column(new_row + first_visible_row, new_col);
+            self.display.stdout.execute(MoveTo(render_col.saturating_sub(1), new_row))?;
         } else if col > 0 {
-            self.buffer_list[self.current_buffer].move_point_to(row + first_visible_row, col - 1);
-            self.buffer_list[self.current_buffer].remove_char()?;
+            // Delete back to the previous grapheme boundary, not just one code point,
+            // so combining sequences and multi-codepoint emoji come out as a unit.
+            let boundary = self.buffer_list[self.current_buffer].previous_grapheme_boundary(at);
+            let removed = self.buffer_list[self.current_buffer].content.slice(boundary..at).to_string();
+            let new_col = point_before.1 - (at - boundary) as u16;
+            self.buffer_list[self.current_buffer].move_point_to(row + first_visible_row, new_col);
+            self.buffer_list[self.current_buffer].delete_range(boundary, at)?;
             self.display_current_buffer()?;
-            self.display.stdout.execute(MoveTo(col -1, row))?;
+            self.push_undo(EditAction::Delete { at: boundary, text: removed }, point_before, (row + first_visible_row, new_col));
+            let render_col = self.buffer_list[self.current_buffer].render_column(row + first_visible_row, new_col);
+            self.display.stdout.execute(MoveTo(render_col, row))?;
         }
         Ok(())
     }
 
     pub fn handle_tab_input(&mut self) -> Result<(), Error> {
         let (col, row) = cursor::position()?;
-        for _i in 0..TAB_SIZE {
-            self.buffer_list[self.current_buffer].write_char(' ')?
+        let point_before = self.buffer_list[self.current_buffer].get_point_line_and_column().unwrap_or((row + self.display.first_line_visible, col));
+        let at = self.buffer_list[self.current_buffer].get_point_offset();
+        self.buffer_list[self.current_buffer].write_char('\t')?;
+        self.display_current_buffer()?;
+        let new_row = row + self.display.first_line_visible;
+        let new_col = point_before.1 + 1;
+        let render_col = self.buffer_list[self.current_buffer].render_column(new_row, new_col);
+        self.buffer_list[self.current_buffer].move_point_to(new_row, new_col);
+        self.push_undo(EditAction::Insert { at, text: "\t".to_string() }, point_before, (new_row, new_col));
+        self.display.stdout.execute(MoveTo(render_col, row))?;
+        Ok(())
+    }
+
+    fn push_undo(&mut self, action: EditAction, point_before: (u16, u16), point_after: (u16, u16)) {
+        if self.current_buffer == 0 {
+            // Scratch input (SaveMode filename prompt, SearchMode query) isn't part
+            // of the document and must not end up on the document's undo stack.
+            return;
+        }
+        self.redo_stack.clear();
+        let coalesced = if let EditAction::Insert { at, text } = &action {
+            text.chars().count() == 1 && text != "\n" && match self.undo_stack.last_mut() {
+                Some(UndoEntry { action: EditAction::Insert { at: last_at, text: last_text }, point_after: last_point_after, .. })
+                    if *last_at + last_text.chars().count() == *at => {
+                    last_text.push_str(text);
+                    *last_point_after = point_after;
+                    true
+                }
+                _ => false,
+            }
+        } else {
+            false
+        };
+
+        if !coalesced {
+            self.undo_stack.push(UndoEntry { action, point_before, point_after });
+        }
+    }
+
+    pub fn handle_undo(&mut self) -> Result<(), Error> {
+        if let Some(entry) = self.undo_stack.pop() {
+            self.apply_undo_entry(&entry, false)?;
+            self.redo_stack.push(entry);
+        }
+        Ok(())
+    }
+
+    pub fn handle_redo(&mut self) -> Result<(), Error> {
+        if let Some(entry) = self.redo_stack.pop() {
+            self.undo_stack.push(entry.clone());
+            self.apply_undo_entry(&entry, true)?;
+        }
+        Ok(())
+    }
+
+    fn apply_undo_entry(&mut self, entry: &UndoEntry, forward: bool) -> Result<(), Error> {
+        let point = if forward {
+            match &entry.action {
+                EditAction::Insert { at, text } => {
+                    self.buffer_list[self.current_buffer].insert_at(*at, text)?;
+                }
+                EditAction::Delete { at, text } => {
+                    self.buffer_list[self.current_buffer].delete_range(*at, *at + text.chars().count())?;
+                }
+            }
+            entry.point_after
+        } else {
+            match &entry.action {
+                EditAction::Insert { at, text } => {
+                    self.buffer_list[self.current_buffer].delete_range(*at, *at + text.chars().count())?;
+                }
+                EditAction::Delete { at, text } => {
+                    self.buffer_list[self.current_buffer].insert_at(*at, text)?;
+                }
+            }
+            entry.point_before
+        };
+        let (row, col) = point;
+        self.buffer_list[self.current_buffer].move_point_to(row, col);
+        self.buffer_list[self.current_buffer].modified = self.undo_stack.len() != self.saved_undo_depth;
+        if row < self.display.first_line_visible {
+            self.display.first_line_visible = row;
+        } else if row - self.display.first_line_visible >= self.display.height {
+            self.display.first_line_visible = row.saturating_sub(self.display.height.saturating_sub(1));
         }
         self.display_current_buffer()?;
-        self.buffer_list[self.current_buffer].move_point_to(row + self.display.first_line_visible, col + TAB_SIZE);
-        self.display.stdout.execute(MoveTo(col + TAB_SIZE, row))?;
+        let render_col = self.buffer_list[self.current_buffer].render_column(row, col);
+        self.display.stdout.execute(MoveTo(render_col, row.saturating_sub(self.display.first_line_visible)))?;
         Ok(())
     }
 
@@ -361,6 +783,18 @@ impl Editor {
         let (start, end) = self.display.get_displayable_lines()?;
         let part = self.buffer_list[self.current_buffer].get_buffer_part(start, end)?;
         self.display.clear_and_print(part)?;
+        self.display_status_bar()?;
+        Ok(())
+    }
+
+    fn display_status_bar(&mut self) -> Result<(), Error> {
+        let buffer = &self.buffer_list[self.current_buffer];
+        let filename = buffer.file_name.clone().unwrap_or_else(|| String::from("[No Name]"));
+        let line_count = buffer.content.len_lines();
+        let (row, col) = buffer.get_point_line_and_column().unwrap_or((0, 0));
+        let modified = if buffer.modified { " [modified]" } else { "" };
+        let status = format!("{} - {} lines - {}:{}{}", filename, line_count, row + 1, col + 1, modified);
+        self.display.print_status_bar(status)?;
         Ok(())
     }
 
@@ -392,12 +826,14 @@ impl Editor {
         self.display.clear_all_display()?;
         if self.current_buffer != 0 {
             if let Some(filename) = self.buffer_list[self.current_buffer].file_name.clone() {
-                let mut file  = OpenOptions::new()
+                let file  = OpenOptions::new()
                     .create(true)
                     .write(true)
                     .truncate(true)
                     .open(filename)?;
-                file.write_all(self.buffer_list[self.current_buffer].content.clone().as_bytes())?;
+                self.buffer_list[self.current_buffer].content.write_to(file)?;
+                self.buffer_list[self.current_buffer].modified = false;
+                self.saved_undo_depth = self.undo_stack.len();
                 self.mode = Normal;
                 self.display_current_buffer()?;
                 execute!(self.display.stdout, RestorePosition)?;
@@ -416,4 +852,116 @@ impl Editor {
         self.display_current_buffer()?;
         Ok(())
     }
+
+    pub fn handle_search_mode_input(&mut self) -> Result<(), Error> {
+        self.search_origin = self.buffer_list[self.current_buffer].get_point_line_and_column();
+        self.search_origin_scroll = self.display.first_line_visible;
+        self.search_anchor = self.buffer_list[self.current_buffer].get_point_offset();
+        self.buffer_list[0].content = Rope::new();
+        self.previous_buffer = self.current_buffer;
+        self.current_buffer = 0;
+        self.mode = SearchMode;
+        execute!(self.display.stdout, SavePosition)?;
+        self.display.print_search_prompt()?;
+        execute!(self.display.stdout, MoveTo(0, 0))?;
+        Ok(())
+    }
+
+    fn handle_search_mode_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<(), Error> {
+        match code {
+            KeyCode::Esc => self.handle_search_cancel()?,
+            KeyCode::Enter => self.handle_search_confirm()?,
+            KeyCode::Backspace => {
+                self.buffer_list[0].remove_char()?;
+                self.run_search(true, false)?;
+            }
+            KeyCode::Char('f') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.run_search(true, true)?;
+            }
+            KeyCode::Char('b') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.run_search(false, true)?;
+            }
+            KeyCode::Char(c) if modifiers.is_empty() || modifiers == KeyModifiers::SHIFT => {
+                self.buffer_list[0].write_char(c)?;
+                self.run_search(true, false)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn run_search(&mut self, forward: bool, advance: bool) -> Result<(), Error> {
+        let query: Vec<char> = self.buffer_list[0].content.chars().collect();
+        if query.is_empty() {
+            return Ok(());
+        }
+        let target = self.previous_buffer;
+        let haystack: Vec<char> = self.buffer_list[target].content.chars().collect();
+        let at = self.buffer_list[target].get_point_offset();
+
+        let found = if forward {
+            let start = if advance { at + 1 } else { self.search_anchor };
+            find_forward(&haystack, &query, start).or_else(|| find_forward(&haystack, &query, 0))
+        } else {
+            let start = if advance { at.saturating_sub(1) } else { self.search_anchor };
+            find_backward(&haystack, &query, start).or_else(|| find_backward(&haystack, &query, haystack.len()))
+        };
+
+        if let Some(pos) = found {
+            let (row, col) = self.buffer_list[target].offset_to_point(pos);
+            self.buffer_list[target].move_point_to(row, col);
+            if row < self.display.first_line_visible {
+                self.display.first_line_visible = row;
+            } else if row - self.display.first_line_visible >= self.display.height {
+                self.display.first_line_visible = row.saturating_sub(self.display.height.saturating_sub(1));
+            }
+        }
+
+        let saved_current = self.current_buffer;
+        self.current_buffer = target;
+        self.display_current_buffer()?;
+        self.current_buffer = saved_current;
+        self.display.print_search_prompt()?;
+        Ok(())
+    }
+
+    fn handle_search_confirm(&mut self) -> Result<(), Error> {
+        self.current_buffer = self.previous_buffer;
+        self.previous_buffer = 0;
+        self.mode = Normal;
+        self.display_current_buffer()?;
+        if let Some((row, col)) = self.buffer_list[self.current_buffer].get_point_line_and_column() {
+            execute!(self.display.stdout, MoveTo(col, row.saturating_sub(self.display.first_line_visible)))?;
+        }
+        Ok(())
+    }
+
+    fn handle_search_cancel(&mut self) -> Result<(), Error> {
+        let target = self.previous_buffer;
+        if let Some((row, col)) = self.search_origin {
+            self.buffer_list[target].move_point_to(row, col);
+        }
+        self.display.first_line_visible = self.search_origin_scroll;
+        self.current_buffer = target;
+        self.previous_buffer = 0;
+        self.mode = Normal;
+        self.display_current_buffer()?;
+        execute!(self.display.stdout, RestorePosition)?;
+        Ok(())
+    }
+}
+
+fn find_forward(haystack: &[char], needle: &[char], from: usize) -> Option<usize> {
+    if needle.is_empty() || haystack.len() < needle.len() || from > haystack.len() - needle.len() {
+        return None;
+    }
+    (from..=haystack.len() - needle.len()).find(|&i| haystack[i..i + needle.len()] == *needle)
+}
+
+fn find_backward(haystack: &[char], needle: &[char], before: usize) -> Option<usize> {
+    if needle.is_empty() || haystack.len() < needle.len() {
+        return None;
+    }
+    let last = before.min(haystack.len() - needle.len());
+    (0..=last).rev().find(|&i| haystack[i..i + needle.len()] == *needle)
 }
\ No newline at end of file